@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::State;
+
+/// A user-defined category used to group and colour calendar events (and,
+/// optionally, notes). The `(id, name, color)` shape mirrors the model the
+/// external calendar crate persists, so categories can replace the single
+/// free-form `color` column the events carry today.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Category {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub workspace_id: String,
+}
+
+#[tauri::command]
+pub async fn list_categories(
+    pool: State<'_, SqlitePool>,
+    workspace_id: String,
+) -> Result<Vec<Category>, String> {
+    sqlx::query_as::<_, Category>(
+        "SELECT id, name, color, workspace_id FROM categories WHERE workspace_id = ? ORDER BY name",
+    )
+    .bind(&workspace_id)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_category(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    name: String,
+    color: String,
+    workspace_id: String,
+) -> Result<Category, String> {
+    sqlx::query("INSERT INTO categories (id, name, color, workspace_id) VALUES (?, ?, ?, ?)")
+        .bind(&id)
+        .bind(&name)
+        .bind(&color)
+        .bind(&workspace_id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Category {
+        id,
+        name,
+        color,
+        workspace_id,
+    })
+}
+
+#[tauri::command]
+pub async fn update_category(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    name: String,
+    color: String,
+) -> Result<(), String> {
+    sqlx::query("UPDATE categories SET name = ?, color = ? WHERE id = ?")
+        .bind(&name)
+        .bind(&color)
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Delete a category, detaching any dependents first so foreign references
+/// never dangle: every calendar event and note pointing at it has its
+/// `category_id` reset to NULL within the same transaction.
+#[tauri::command]
+pub async fn delete_category(pool: State<'_, SqlitePool>, id: String) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query("UPDATE calendarEvents SET category_id = NULL WHERE category_id = ?")
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query("UPDATE notes SET category_id = NULL WHERE category_id = ?")
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query("DELETE FROM categories WHERE id = ?")
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
@@ -0,0 +1,361 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+use tauri::State;
+
+/// Weekday abbreviations used by RFC 5545 `BYDAY`, indexed by JavaScript's
+/// `Date.getDay()` convention (0 = Sunday) — the same order `repeat_on` stores.
+const BYDAY: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+
+/// Escape a TEXT property value per RFC 5545 §3.3.11 so separators and newlines
+/// in user content can't terminate the property or the VEVENT early.
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Inverse of [`escape_text`], applied to imported TEXT values.
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct EventRow {
+    id: String,
+    date: String,
+    title: String,
+    time: Option<String>,
+    color: Option<String>,
+    repeat: Option<String>,
+    repeat_on: Option<String>,
+    repeat_end: Option<String>,
+    exceptions: Option<String>,
+}
+
+fn nonempty(v: &Option<String>) -> Option<&str> {
+    v.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// `YYYY-MM-DD` → `YYYYMMDD`.
+fn ical_date(iso: &str) -> String {
+    iso.replace('-', "")
+}
+
+/// `YYYYMMDD` (optionally with a `THHMMSS` suffix) → `(YYYY-MM-DD, Option<HH:MM>)`.
+fn from_ical_date(value: &str) -> (String, Option<String>) {
+    let (day, time) = match value.split_once('T') {
+        Some((d, t)) => (d, Some(t)),
+        None => (value, None),
+    };
+    let date = if day.len() == 8 {
+        format!("{}-{}-{}", &day[0..4], &day[4..6], &day[6..8])
+    } else {
+        day.to_string()
+    };
+    let time = time.filter(|t| t.len() >= 4).map(|t| format!("{}:{}", &t[0..2], &t[2..4]));
+    (date, time)
+}
+
+fn build_rrule(row: &EventRow) -> Option<String> {
+    let freq = match nonempty(&row.repeat)? {
+        "daily" => "DAILY",
+        "weekly" => "WEEKLY",
+        "monthly" => "MONTHLY",
+        "yearly" => "YEARLY",
+        _ => return None,
+    };
+    let mut rule = format!("FREQ={freq}");
+
+    if freq == "WEEKLY" {
+        if let Some(raw) = nonempty(&row.repeat_on) {
+            if let Ok(days) = serde_json::from_str::<Vec<usize>>(raw) {
+                let byday: Vec<&str> = days.iter().filter_map(|&d| BYDAY.get(d).copied()).collect();
+                if !byday.is_empty() {
+                    rule.push_str(&format!(";BYDAY={}", byday.join(",")));
+                }
+            }
+        }
+    }
+
+    if let Some(end) = nonempty(&row.repeat_end) {
+        // UNTIL's value type must match DTSTART: a timed event carries its time
+        // through so strict parsers don't reject a DATE UNTIL on a DATE-TIME rule.
+        match nonempty(&row.time) {
+            Some(time) => {
+                rule.push_str(&format!(";UNTIL={}T{}00", ical_date(end), time.replace(':', "")))
+            }
+            None => rule.push_str(&format!(";UNTIL={}", ical_date(end))),
+        }
+    }
+    Some(rule)
+}
+
+#[tauri::command]
+pub async fn export_ics(pool: State<'_, SqlitePool>, workspace_id: String) -> Result<String, String> {
+    let rows = sqlx::query_as::<_, EventRow>(
+        "SELECT id, date, title, time, color, repeat, repeat_on, repeat_end, exceptions \
+         FROM calendarEvents WHERE workspace_id = ?",
+    )
+    .bind(&workspace_id)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // RFC 5545 requires a DTSTAMP in every VEVENT; one stamp for the export run.
+    let dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut out = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//neuronotes//EN\r\n");
+    for row in &rows {
+        out.push_str("BEGIN:VEVENT\r\n");
+        out.push_str(&format!("UID:{}\r\n", row.id));
+        out.push_str(&format!("DTSTAMP:{dtstamp}\r\n"));
+        out.push_str(&format!("SUMMARY:{}\r\n", escape_text(&row.title)));
+
+        // A timed event carries its time through to EXDATE so the EXDATE value
+        // type matches DTSTART (parsers reject a DATE EXDATE on a DATE-TIME).
+        let time = nonempty(&row.time);
+        match time {
+            Some(time) => out.push_str(&format!(
+                "DTSTART:{}T{}00\r\n",
+                ical_date(&row.date),
+                time.replace(':', "")
+            )),
+            None => out.push_str(&format!("DTSTART;VALUE=DATE:{}\r\n", ical_date(&row.date))),
+        }
+
+        if let Some(rrule) = build_rrule(row) {
+            out.push_str(&format!("RRULE:{rrule}\r\n"));
+        }
+
+        if let Some(raw) = nonempty(&row.exceptions) {
+            if let Ok(dates) = serde_json::from_str::<Vec<String>>(raw) {
+                if !dates.is_empty() {
+                    match time {
+                        Some(time) => {
+                            let suffix = time.replace(':', "");
+                            let dates: Vec<String> = dates
+                                .iter()
+                                .map(|d| format!("{}T{}00", ical_date(d), suffix))
+                                .collect();
+                            out.push_str(&format!("EXDATE:{}\r\n", dates.join(",")));
+                        }
+                        None => {
+                            let dates: Vec<String> = dates.iter().map(|d| ical_date(d)).collect();
+                            out.push_str(&format!("EXDATE;VALUE=DATE:{}\r\n", dates.join(",")));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(color) = nonempty(&row.color) {
+            out.push_str(&format!("X-APPLE-CALENDAR-COLOR:{}\r\n", escape_text(color)));
+        }
+
+        out.push_str("END:VEVENT\r\n");
+    }
+    out.push_str("END:VCALENDAR\r\n");
+    Ok(fold_lines(&out))
+}
+
+/// RFC 5545 §3.1: wrap content lines longer than 75 octets onto continuation
+/// lines prefixed with a single space, the inverse of the importer's unfold.
+fn fold_lines(ics: &str) -> String {
+    let mut out = String::with_capacity(ics.len());
+    for line in ics.split("\r\n") {
+        let bytes = line.as_bytes();
+        if bytes.len() <= 75 {
+            out.push_str(line);
+            out.push_str("\r\n");
+            continue;
+        }
+        let mut start = 0;
+        let mut first = true;
+        while start < bytes.len() {
+            // Keep each octet run under the limit (74 after the leading space on
+            // continuations); never split inside a multi-byte UTF-8 sequence.
+            let budget = if first { 75 } else { 74 };
+            let mut take = (bytes.len() - start).min(budget);
+            while take > 0 && start + take < bytes.len() && (bytes[start + take] & 0xC0) == 0x80 {
+                take -= 1;
+            }
+            if !first {
+                out.push(' ');
+            }
+            out.push_str(&line[start..start + take]);
+            out.push_str("\r\n");
+            start += take;
+            first = false;
+        }
+    }
+    // `split` yields a trailing empty element after the final CRLF; drop its echo.
+    out.truncate(out.trim_end_matches("\r\n").len() + 2);
+    out
+}
+
+#[derive(Default)]
+struct ParsedEvent {
+    uid: Option<String>,
+    summary: Option<String>,
+    date: Option<String>,
+    time: Option<String>,
+    color: Option<String>,
+    repeat: Option<String>,
+    repeat_on: Option<String>,
+    repeat_end: Option<String>,
+    exceptions: Option<String>,
+}
+
+fn parse_rrule(value: &str, ev: &mut ParsedEvent) {
+    for part in value.split(';') {
+        let Some((key, val)) = part.split_once('=') else { continue };
+        match key {
+            "FREQ" => {
+                ev.repeat = Some(
+                    match val {
+                        "DAILY" => "daily",
+                        "WEEKLY" => "weekly",
+                        "MONTHLY" => "monthly",
+                        "YEARLY" => "yearly",
+                        _ => continue,
+                    }
+                    .to_string(),
+                );
+            }
+            "BYDAY" => {
+                let days: Vec<usize> = val
+                    .split(',')
+                    .filter_map(|d| BYDAY.iter().position(|b| *b == d))
+                    .collect();
+                if let Ok(json) = serde_json::to_string(&days) {
+                    ev.repeat_on = Some(json);
+                }
+            }
+            "UNTIL" => {
+                let (date, _) = from_ical_date(val);
+                ev.repeat_end = Some(date);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn import_ics(
+    pool: State<'_, SqlitePool>,
+    workspace_id: String,
+    text: String,
+) -> Result<(), String> {
+    let mut current: Option<ParsedEvent> = None;
+
+    // RFC 5545 §3.1: lines longer than 75 octets are folded onto continuation
+    // lines beginning with a space or tab. Unfold before parsing properties.
+    let mut unfolded: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match raw.strip_prefix(|c| c == ' ' || c == '\t') {
+            Some(cont) if !unfolded.is_empty() => unfolded.last_mut().unwrap().push_str(cont),
+            _ => unfolded.push(raw.to_string()),
+        }
+    }
+
+    for line in &unfolded {
+        let line = line.as_str();
+        match line {
+            "BEGIN:VEVENT" => current = Some(ParsedEvent::default()),
+            "END:VEVENT" => {
+                if let Some(ev) = current.take() {
+                    upsert_event(pool.inner(), &workspace_id, ev).await?;
+                }
+            }
+            _ => {
+                let Some(ev) = current.as_mut() else { continue };
+                // Split off any property parameters (e.g. `DTSTART;VALUE=DATE`).
+                let (name_params, value) = match line.split_once(':') {
+                    Some(pair) => pair,
+                    None => continue,
+                };
+                let name = name_params.split(';').next().unwrap_or(name_params);
+                match name {
+                    "UID" => ev.uid = Some(value.to_string()),
+                    "SUMMARY" => ev.summary = Some(unescape_text(value)),
+                    "DTSTART" => {
+                        let (date, time) = from_ical_date(value);
+                        ev.date = Some(date);
+                        ev.time = time;
+                    }
+                    "RRULE" => parse_rrule(value, ev),
+                    "EXDATE" => {
+                        let dates: Vec<String> =
+                            value.split(',').map(|d| from_ical_date(d).0).collect();
+                        if let Ok(json) = serde_json::to_string(&dates) {
+                            ev.exceptions = Some(json);
+                        }
+                    }
+                    "X-APPLE-CALENDAR-COLOR" | "CATEGORIES" => {
+                        ev.color = Some(unescape_text(value))
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Insert or replace a row keyed on its UID, so re-importing the same file is
+/// idempotent rather than producing duplicates.
+async fn upsert_event(
+    pool: &SqlitePool,
+    workspace_id: &str,
+    ev: ParsedEvent,
+) -> Result<(), String> {
+    let (Some(id), Some(date), Some(title)) = (ev.uid, ev.date, ev.summary) else {
+        return Ok(());
+    };
+
+    // Update only the ICS-derived columns on conflict so a re-import preserves
+    // `category_id` (which ICS can't carry). The WHERE guard keeps an import into
+    // one workspace from rewriting an event that already exists in another.
+    sqlx::query(
+        "INSERT INTO calendarEvents \
+         (id, date, title, time, workspace_id, repeat, repeat_on, repeat_end, exceptions, color) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET \
+           date = excluded.date, title = excluded.title, time = excluded.time, \
+           repeat = excluded.repeat, repeat_on = excluded.repeat_on, \
+           repeat_end = excluded.repeat_end, exceptions = excluded.exceptions, \
+           color = excluded.color \
+         WHERE calendarEvents.workspace_id = excluded.workspace_id",
+    )
+    .bind(id)
+    .bind(date)
+    .bind(title)
+    .bind(ev.time)
+    .bind(workspace_id)
+    .bind(ev.repeat)
+    .bind(ev.repeat_on)
+    .bind(ev.repeat_end)
+    .bind(ev.exceptions)
+    .bind(ev.color)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
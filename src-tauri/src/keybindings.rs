@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, HashMap};
+
+use sqlx::SqlitePool;
+use tauri::State;
+
+/// The `settings` row key under which the action→chord map is persisted.
+const SETTINGS_KEY: &str = "keybindings";
+
+/// The default action→chord map, seeded by migration 5 and restored by
+/// [`reset_keybindings`]. Kept in sync with the blob the migration inserts.
+pub const DEFAULT_KEYBINDINGS: &str = r#"{
+    "new_note": "Ctrl+N",
+    "new_folder": "Ctrl+Shift+N",
+    "save": "Ctrl+S",
+    "search": "Ctrl+F",
+    "command_palette": "Ctrl+P",
+    "toggle_sidebar": "Ctrl+B",
+    "next_note": "Ctrl+J",
+    "prev_note": "Ctrl+K",
+    "delete": "Delete",
+    "settings": "Ctrl+,"
+}"#;
+
+fn defaults() -> HashMap<String, String> {
+    serde_json::from_str(DEFAULT_KEYBINDINGS).expect("default keybindings are valid JSON")
+}
+
+async fn load(pool: &SqlitePool) -> Result<HashMap<String, String>, String> {
+    let stored: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(SETTINGS_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match stored.and_then(|(v,)| serde_json::from_str(&v).ok()) {
+        Some(map) => Ok(map),
+        None => Ok(defaults()),
+    }
+}
+
+async fn save(pool: &SqlitePool, map: &HashMap<String, String>) -> Result<(), String> {
+    let value = serde_json::to_string(map).map_err(|e| e.to_string())?;
+    sqlx::query("INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)")
+        .bind(SETTINGS_KEY)
+        .bind(value)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Group actions by the chord they are bound to and return every group where
+/// two or more actions collide. An empty result means the map is conflict-free.
+pub fn find_conflicts(bindings: &HashMap<String, String>) -> Vec<Vec<String>> {
+    let mut by_chord: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (action, chord) in bindings {
+        by_chord.entry(chord.as_str()).or_default().push(action.clone());
+    }
+    by_chord
+        .into_values()
+        .filter(|actions| actions.len() > 1)
+        .map(|mut actions| {
+            actions.sort();
+            actions
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_keybindings(
+    pool: State<'_, SqlitePool>,
+) -> Result<HashMap<String, String>, String> {
+    load(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn set_keybinding(
+    pool: State<'_, SqlitePool>,
+    action: String,
+    chord: String,
+) -> Result<(), String> {
+    let mut map = load(pool.inner()).await?;
+    map.insert(action, chord);
+    save(pool.inner(), &map).await
+}
+
+#[tauri::command]
+pub async fn reset_keybindings(pool: State<'_, SqlitePool>) -> Result<(), String> {
+    save(pool.inner(), &defaults()).await
+}
+
+#[tauri::command]
+pub fn validate_keybindings(bindings: HashMap<String, String>) -> Vec<Vec<String>> {
+    find_conflicts(&bindings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_have_no_overlapping_chords() {
+        assert!(
+            find_conflicts(&defaults()).is_empty(),
+            "default keybindings must not bind two actions to the same chord"
+        );
+    }
+
+    #[test]
+    fn conflicts_are_detected() {
+        let mut map = HashMap::new();
+        map.insert("save".to_string(), "Ctrl+S".to_string());
+        map.insert("search".to_string(), "Ctrl+S".to_string());
+        assert_eq!(find_conflicts(&map), vec![vec!["save".to_string(), "search".to_string()]]);
+    }
+}
@@ -3,8 +3,15 @@
     windows_subsystem = "windows"
 )]
 
-use tauri_plugin_sql::{Migration, MigrationKind};
+use sqlx::sqlite::SqlitePoolOptions;
+use tauri::Manager;
 use tauri_plugin_fs::init as init_fs;
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+mod categories;
+mod ics;
+mod keybindings;
+mod recurrence;
 
 fn get_migrations() -> Vec<Migration> {
     vec![
@@ -39,10 +46,48 @@ fn get_migrations() -> Vec<Migration> {
                 ALTER TABLE calendarEvents ADD COLUMN color TEXT;
             "#,
             kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "add_event_categories",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS categories (id TEXT PRIMARY KEY, name TEXT NOT NULL, color TEXT NOT NULL, workspace_id TEXT NOT NULL);
+                ALTER TABLE calendarEvents ADD COLUMN category_id TEXT;
+                ALTER TABLE notes ADD COLUMN category_id TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "seed_default_keybindings",
+            sql: r#"
+                INSERT OR IGNORE INTO settings (key, value) VALUES ('keybindings', '{"new_note":"Ctrl+N","new_folder":"Ctrl+Shift+N","save":"Ctrl+S","search":"Ctrl+F","command_palette":"Ctrl+P","toggle_sidebar":"Ctrl+B","next_note":"Ctrl+J","prev_note":"Ctrl+K","delete":"Delete","settings":"Ctrl+,"}');
+            "#,
+            kind: MigrationKind::Up,
         }
     ]
 }
 
+/// Open a pool against the same sqlite file the SQL plugin migrates, so the
+/// command handlers and the frontend's direct queries share one database.
+async fn open_pool(app: &tauri::AppHandle) -> Result<sqlx::SqlitePool, Box<dyn std::error::Error>> {
+    let mut path = app.path().app_config_dir()?;
+    std::fs::create_dir_all(&path)?;
+    // Key the file to the build profile so the backend opens the same database
+    // the frontend does: dev builds use `neuronotes_dev.db`, release `neuronotes.db`.
+    let db_file = if cfg!(debug_assertions) {
+        "neuronotes_dev.db"
+    } else {
+        "neuronotes.db"
+    };
+    path.push(db_file);
+
+    let pool = SqlitePoolOptions::new()
+        .connect(&format!("sqlite:{}?mode=rwc", path.display()))
+        .await?;
+    Ok(pool)
+}
+
 fn main() {
     tauri::Builder::default()
         .plugin(
@@ -54,6 +99,25 @@ fn main() {
         .plugin(tauri_plugin_shell::init())
         .plugin(init_fs())
         .plugin(tauri_plugin_log::Builder::default().build())
+        .setup(|app| {
+            let handle = app.handle().clone();
+            let pool = tauri::async_runtime::block_on(open_pool(&handle))?;
+            app.manage(pool);
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            categories::list_categories,
+            categories::create_category,
+            categories::update_category,
+            categories::delete_category,
+            recurrence::expand_events,
+            ics::export_ics,
+            ics::import_ics,
+            keybindings::get_keybindings,
+            keybindings::set_keybinding,
+            keybindings::reset_keybindings,
+            keybindings::validate_keybindings,
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,236 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::State;
+
+/// A calendar event row as persisted, including the repeat-rule columns added
+/// in migration 2 and the category link added in migration 4.
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct EventRow {
+    id: String,
+    date: String,
+    title: String,
+    time: Option<String>,
+    color: Option<String>,
+    category_id: Option<String>,
+    repeat: Option<String>,
+    repeat_on: Option<String>,
+    repeat_end: Option<String>,
+    exceptions: Option<String>,
+}
+
+/// A single materialized occurrence. `event_id` points back at the parent row
+/// so the frontend can open the underlying event when an instance is clicked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventInstance {
+    pub event_id: String,
+    pub date: String,
+    pub title: String,
+    pub time: Option<String>,
+    pub color: Option<String>,
+    pub category_id: Option<String>,
+}
+
+const ISO: &str = "%Y-%m-%d";
+
+impl EventRow {
+    fn instance(&self, date: NaiveDate) -> EventInstance {
+        EventInstance {
+            event_id: self.id.clone(),
+            date: date.format(ISO).to_string(),
+            title: self.title.clone(),
+            time: self.time.clone(),
+            color: self.color.clone(),
+            category_id: self.category_id.clone(),
+        }
+    }
+}
+
+/// The `n`-th monthly occurrence of `base`, always measured from the base
+/// day-of-month and clamped to the target month (e.g. Jan 31 → Feb 28, Mar 31,
+/// Apr 30, …). Computing from the base avoids the drift a mutated cursor would
+/// accumulate across short months.
+fn nth_month(base: NaiveDate, n: i32) -> NaiveDate {
+    let total = base.month() as i32 - 1 + n;
+    let year = base.year() + total.div_euclid(12);
+    let month = total.rem_euclid(12) as u32 + 1;
+    clamp_day(year, month, base.day())
+}
+
+/// The `n`-th yearly occurrence of `base`, measured from the base month/day so
+/// a Feb 29 event returns to Feb 29 on the next leap year rather than sticking
+/// at Feb 28.
+fn nth_year(base: NaiveDate, n: i32) -> NaiveDate {
+    clamp_day(base.year() + n, base.month(), base.day())
+}
+
+fn clamp_day(year: i32, month: u32, day: u32) -> NaiveDate {
+    let mut day = day;
+    loop {
+        if let Some(d) = NaiveDate::from_ymd_opt(year, month, day) {
+            return d;
+        }
+        day -= 1;
+    }
+}
+
+#[tauri::command]
+pub async fn expand_events(
+    pool: State<'_, SqlitePool>,
+    workspace_id: String,
+    range_start: String,
+    range_end: String,
+) -> Result<Vec<EventInstance>, String> {
+    let start = NaiveDate::parse_from_str(&range_start, ISO).map_err(|e| e.to_string())?;
+    let end = NaiveDate::parse_from_str(&range_end, ISO).map_err(|e| e.to_string())?;
+
+    let rows = sqlx::query_as::<_, EventRow>(
+        "SELECT id, date, title, time, color, category_id, repeat, repeat_on, repeat_end, exceptions \
+         FROM calendarEvents WHERE workspace_id = ?",
+    )
+    .bind(&workspace_id)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut out = Vec::new();
+    for row in &rows {
+        expand_row(row, start, end, &mut out)?;
+    }
+    Ok(out)
+}
+
+fn expand_row(
+    row: &EventRow,
+    start: NaiveDate,
+    end: NaiveDate,
+    out: &mut Vec<EventInstance>,
+) -> Result<(), String> {
+    let base = NaiveDate::parse_from_str(&row.date, ISO).map_err(|e| e.to_string())?;
+
+    // A base date beyond the window can never produce an occurrence inside it.
+    if base > end {
+        return Ok(());
+    }
+
+    let repeat = row.repeat.as_deref().unwrap_or("").trim();
+    if repeat.is_empty() {
+        if base >= start {
+            out.push(row.instance(base));
+        }
+        return Ok(());
+    }
+
+    // The iteration never runs past the earlier of the rule's own end and the
+    // requested window.
+    let repeat_end = row
+        .repeat_end
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| NaiveDate::parse_from_str(s.trim(), ISO))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let limit = match repeat_end {
+        Some(re) if re < end => re,
+        _ => end,
+    };
+
+    let exceptions = parse_exceptions(row.exceptions.as_deref())?;
+
+    if repeat == "weekly" {
+        expand_weekly(row, base, start, limit, &exceptions, out)?;
+        return Ok(());
+    }
+
+    // An unrecognized frequency (typo, or a value this build doesn't handle yet)
+    // falls back to the base occurrence so the event never vanishes entirely.
+    if !matches!(repeat, "daily" | "monthly" | "yearly") {
+        if base >= start && !exceptions.contains(&base) {
+            out.push(row.instance(base));
+        }
+        return Ok(());
+    }
+
+    // Each occurrence is computed from `base` (not a mutated cursor) so the
+    // day-of-month never drifts across short months.
+    // The cursor advances monotonically from `base`, so `cursor > limit` is the
+    // sole, correct stopping condition — no arbitrary cap that could truncate a
+    // long-but-finite window mid-stream.
+    let mut n: i32 = 0;
+    loop {
+        let cursor = match repeat {
+            "daily" => base + Duration::days(n as i64),
+            "monthly" => nth_month(base, n),
+            "yearly" => nth_year(base, n),
+            _ => break,
+        };
+        if cursor > limit {
+            break;
+        }
+        n += 1;
+        // Occurrences before the window are skipped but still advance the cursor.
+        if cursor >= start && !exceptions.contains(&cursor) {
+            out.push(row.instance(cursor));
+        }
+    }
+    Ok(())
+}
+
+/// Weekly recurrence emits one occurrence per selected weekday within each
+/// week. `repeat_on` is a JSON array of weekday indices following JavaScript's
+/// `Date.getDay()` convention (0 = Sunday). An empty/absent list falls back to
+/// the base event's own weekday.
+fn expand_weekly(
+    row: &EventRow,
+    base: NaiveDate,
+    start: NaiveDate,
+    limit: NaiveDate,
+    exceptions: &[NaiveDate],
+    out: &mut Vec<EventInstance>,
+) -> Result<(), String> {
+    let mut weekdays: Vec<u32> = row
+        .repeat_on
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .map(serde_json::from_str::<Vec<u32>>)
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+    if weekdays.is_empty() {
+        weekdays.push(base.weekday().num_days_from_sunday());
+    }
+
+    // `anchor` steps forward a week at a time and `limit` bounds the window, so
+    // the `anchor > limit` check is the only stopping condition needed.
+    let mut anchor = base;
+    loop {
+        if anchor > limit {
+            break;
+        }
+        for &wd in &weekdays {
+            let offset = (wd + 7 - anchor.weekday().num_days_from_sunday()) % 7;
+            let date = anchor + Duration::days(offset as i64);
+            if date < base || date > limit {
+                continue;
+            }
+            if date >= start && !exceptions.contains(&date) {
+                out.push(row.instance(date));
+            }
+        }
+        anchor += Duration::days(7);
+    }
+    Ok(())
+}
+
+/// Parse the `exceptions` column — a JSON array of ISO dates marking deleted or
+/// moved instances to skip.
+fn parse_exceptions(raw: Option<&str>) -> Result<Vec<NaiveDate>, String> {
+    let Some(raw) = raw.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let dates: Vec<String> = serde_json::from_str(raw).map_err(|e| e.to_string())?;
+    dates
+        .iter()
+        .map(|d| NaiveDate::parse_from_str(d, ISO).map_err(|e| e.to_string()))
+        .collect()
+}